@@ -1,4 +1,4 @@
-use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::delay::DelayNs;
 use hal::{Delay, I2cdev};
 use linux_embedded_hal as hal;
 
@@ -13,12 +13,15 @@ fn main() {
     println!(" Serial number: {}", sn);
     sensor.execute_self_test().unwrap();
 
-    sensor.execute_conditioning().unwrap();
+    let mut sensor = match sensor.execute_conditioning() {
+        Ok(sensor) => sensor,
+        Err(e) => panic!("conditioning failed: {:?}", e.error),
+    };
     println!("Start measurement");
 
     loop {
         let data = sensor.measure_raw().unwrap();
         println!("VOC ticks: {}, NOx ticks: {}", data.voc_ticks, data.nox_ticks);
-        hal::Delay.delay_ms(1000u16);
+        hal::Delay.delay_ms(1000u32);
     }
 }
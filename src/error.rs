@@ -0,0 +1,56 @@
+use embedded_hal::i2c::I2c;
+use sensirion_i2c::i2c;
+
+/// All errors that can be returned by the driver.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+    /// I2C bus error.
+    I2c(E),
+    /// A received word failed its CRC8 checksum.
+    Crc,
+    /// The built-in self-test reported a defect.
+    SelfTest(SelfTestError),
+}
+
+/// Error returned by a fallible state transition.
+///
+/// The attempted command failed on the bus, so the sensor is handed back
+/// unchanged in `dev` rather than being lost.
+pub struct ModeChangeError<E, Dev> {
+    /// The underlying error that prevented the transition.
+    pub error: Error<E>,
+    /// The sensor, still in its original state.
+    pub dev: Dev,
+}
+
+impl<E: core::fmt::Debug, Dev> core::fmt::Debug for ModeChangeError<E, Dev> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ModeChangeError")
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// Result of the built-in hotplate/MOX self-test.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SelfTestError {
+    /// The VOC pixel failed.
+    Voc,
+    /// The NOx pixel failed.
+    Nox,
+    /// Both pixels failed.
+    All,
+    /// The returned test pattern was not recognized.
+    Undefined,
+}
+
+impl<I: I2c> From<i2c::Error<I>> for Error<I::Error> {
+    fn from(err: i2c::Error<I>) -> Self {
+        match err {
+            i2c::Error::I2cWrite(e) | i2c::Error::I2cRead(e) => Error::I2c(e),
+            i2c::Error::Crc => Error::Crc,
+        }
+    }
+}
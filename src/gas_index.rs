@@ -0,0 +1,182 @@
+//! VOC/NOx gas index algorithm.
+//!
+//! The raw signals returned by [`measure_raw`](crate::sgp41::Sgp41::measure_raw)
+//! are uncalibrated MOX ticks that slowly drift with the sensor baseline. This
+//! module turns one raw tick value per second into a processed gas index, the
+//! same way the sibling `sgp40` driver does: VOC has a nominal index of 100
+//! (range 1..=500) and NOx a nominal index of 1.
+//!
+//! A separate [`GasIndexAlgorithm`] instance is kept per channel because VOC and
+//! NOx use different tuning. Call [`process`](GasIndexAlgorithm::process) once
+//! per second with the latest raw value.
+
+use libm::{expf, logf, sqrtf};
+
+/// Sampling interval the algorithm is tuned for, in seconds.
+const SAMPLING_INTERVAL: f32 = 1.0;
+/// Number of initial samples that only seed the baseline and emit the nominal
+/// index.
+const INITIAL_BLACKOUT: u32 = 45;
+/// Initial adaptive dispersion, also used as a lower bound so the normalized
+/// offset `x = offset / std` can never divide by a collapsed dispersion.
+const INITIAL_STD: f32 = 50.0;
+/// Upper bound on the adaptive gain during warm-up. Without it the very first
+/// post-blackout sample would use `gamma = 1`, wiping the dispersion to the
+/// single-sample deviation (zero when the baseline was just seeded).
+const WARMUP_GAMMA_MAX: f32 = 0.5;
+
+/// Fixed per-channel tuning.
+struct Params {
+    /// Nominal index emitted for clean air (100 for VOC, 1 for NOx).
+    nominal: f32,
+    /// Mean time constant in seconds (~12 h for VOC, shorter for NOx).
+    mean_tau: f32,
+    /// Output low-pass time constant in seconds.
+    output_tau: f32,
+    /// Sigmoid steepness.
+    k: f32,
+    /// Sigmoid center, derived so that `x = 0` maps onto `nominal`.
+    x0: f32,
+    /// Invert the offset sign (VOC resistance falls as VOC rises).
+    invert: bool,
+    /// Index above which the channel is considered to be in a gas event.
+    gating_threshold: f32,
+    /// Maximum gas-event duration, in samples, before baseline adaptation is
+    /// frozen to prevent creep.
+    gating_max_duration: u32,
+}
+
+impl Params {
+    fn voc() -> Self {
+        let nominal = 100.0;
+        let k = 6.0;
+        Params {
+            nominal,
+            mean_tau: 12.0 * 3600.0,
+            output_tau: 3.0,
+            k,
+            x0: center(nominal, k),
+            invert: true,
+            gating_threshold: 2.0 * nominal,
+            gating_max_duration: 180,
+        }
+    }
+
+    fn nox() -> Self {
+        let nominal = 1.0;
+        let k = 6.0;
+        Params {
+            nominal,
+            mean_tau: 3.0 * 3600.0,
+            output_tau: 3.0,
+            k,
+            x0: center(nominal, k),
+            invert: false,
+            gating_threshold: 20.0,
+            gating_max_duration: 180,
+        }
+    }
+}
+
+/// Solve the sigmoid center `x0` so that `500 / (1 + exp(-k * (0 - x0)))`
+/// equals the channel's nominal index.
+fn center(nominal: f32, k: f32) -> f32 {
+    logf(500.0 / nominal - 1.0) / k
+}
+
+/// Adaptive gas index algorithm for a single channel.
+pub struct GasIndexAlgorithm {
+    params: Params,
+    /// Adaptive baseline.
+    mean: f32,
+    /// Adaptive dispersion.
+    std: f32,
+    /// Low-pass filtered output index.
+    index: f32,
+    /// Number of processed samples.
+    samples: u32,
+    /// Number of consecutive samples spent above `gating_threshold`.
+    gating_duration: u32,
+}
+
+impl GasIndexAlgorithm {
+    fn new(params: Params) -> Self {
+        let nominal = params.nominal;
+        GasIndexAlgorithm {
+            params,
+            mean: 0.0,
+            std: INITIAL_STD,
+            index: nominal,
+            samples: 0,
+            gating_duration: 0,
+        }
+    }
+
+    /// Creates an instance tuned for the VOC channel (nominal index 100).
+    pub fn new_voc() -> Self {
+        Self::new(Params::voc())
+    }
+
+    /// Creates an instance tuned for the NOx channel (nominal index 1).
+    pub fn new_nox() -> Self {
+        Self::new(Params::nox())
+    }
+
+    /// Feeds one raw tick value and returns the processed gas index.
+    ///
+    /// Must be called once per second for the baseline to stay valid.
+    pub fn process(&mut self, raw: u16) -> i32 {
+        let sraw = raw as f32;
+
+        // Initial blackout: only seed the baseline.
+        if self.samples < INITIAL_BLACKOUT {
+            self.mean = sraw;
+            self.samples += 1;
+            return self.params.nominal as i32;
+        }
+
+        let offset = sraw - self.mean;
+        let mut x = offset / self.std;
+        if self.params.invert {
+            x = -x;
+        }
+
+        // Sigmoid, already scaled so that x = 0 lands on the nominal index.
+        let raw_index = 500.0 / (1.0 + expf(-self.params.k * (x - self.params.x0)));
+
+        // Short output low-pass.
+        let alpha = 1.0 - expf(-SAMPLING_INTERVAL / self.params.output_tau);
+        self.index += alpha * (raw_index - self.index);
+
+        // Gating: freeze baseline adaptation during sustained gas events.
+        if self.index > self.params.gating_threshold {
+            self.gating_duration += 1;
+        } else {
+            self.gating_duration = 0;
+        }
+        let gated = self.gating_duration > self.params.gating_max_duration;
+
+        if !gated {
+            // Adaptive gain: large right after blackout, relaxing towards the
+            // steady-state value `SAMPLING_INTERVAL / mean_tau`.
+            let steady = SAMPLING_INTERVAL / self.params.mean_tau;
+            let n = (self.samples - INITIAL_BLACKOUT) as f32;
+            let gamma = steady.max(1.0 / (n + 1.0)).min(WARMUP_GAMMA_MAX);
+
+            self.std =
+                sqrtf((1.0 - gamma) * self.std * self.std + gamma * offset * offset).max(INITIAL_STD);
+            self.mean += gamma * offset;
+        }
+
+        self.samples += 1;
+
+        let index = self.index;
+        if index < 1.0 {
+            1
+        } else if index > 500.0 {
+            500
+        } else {
+            index as i32
+        }
+    }
+}
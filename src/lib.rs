@@ -9,4 +9,5 @@
 pub mod sgp41;
 pub mod commands;
 pub mod error;
+pub mod gas_index;
 pub mod types;
\ No newline at end of file
@@ -1,80 +1,82 @@
 use crate::commands::Command;
-use crate::error::{Error, SelfTestError};
+use crate::error::{Error, ModeChangeError, SelfTestError};
 use crate::types::RawSensorData;
-use embedded_hal as hal;
-use hal::blocking::delay::DelayMs;
-use hal::blocking::i2c::{Read, Write, WriteRead};
+use core::marker::PhantomData;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
 use sensirion_i2c::{crc8, i2c};
 
 const SGP41_I2C_ADDRESS: u8 = 0x59;
 
-#[derive(Debug, Default)]
-pub struct Sgp41<I2C, D> {
+/// Default conditioning window; the datasheet allows at most 10 s.
+const DEFAULT_CONDITIONING_MS: u32 = 10_000;
+
+/// Marker types for the sensor's command lifecycle.
+pub mod mode {
+    /// Heater off, idle mode. The only state in which a fresh sensor starts.
+    pub struct Idle;
+    /// Conditioning running (VOC pixel only); raw signals may already be read.
+    pub struct Conditioning;
+    /// Both pixels measuring at the nominal 1 Hz cadence.
+    pub struct Measuring;
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for super::mode::Conditioning {}
+    impl Sealed for super::mode::Measuring {}
+}
+
+/// States in which the hotplate is powered and raw signals can be read.
+pub trait MeasurementState: private::Sealed {}
+impl MeasurementState for mode::Conditioning {}
+impl MeasurementState for mode::Measuring {}
+
+/// SGP41 driver, parameterized by the command-lifecycle state it is in.
+///
+/// The datasheet mandates a fixed sequence — conditioning before measurement,
+/// heater off back to idle — which is encoded here in the type system:
+/// [`execute_conditioning`](Sgp41::execute_conditioning) consumes an [`Idle`]
+/// sensor and returns a [`Conditioning`] one, raw measurements are only
+/// available while the heater is on, and [`turn_heater_off`](Sgp41::turn_heater_off)
+/// returns to [`Idle`].
+///
+/// [`Idle`]: mode::Idle
+/// [`Conditioning`]: mode::Conditioning
+#[derive(Debug)]
+pub struct Sgp41<I2C, D, State = mode::Idle> {
     i2c: I2C,
     delay: D,
     // useful in case of presence heat sources on the PCB (battery charger, motor, etc)
     temperature_offset: i16,
+    // relative humidity automatically fed into compensated measurements
+    default_humidity: Option<u8>,
+    // recommended conditioning window, bounded to 10 s by the datasheet
+    conditioning_duration_ms: u32,
+    _state: PhantomData<State>,
 }
 
-impl<I2C, D, E> Sgp41<I2C, D>
+impl<I2C, D, E, State> Sgp41<I2C, D, State>
 where
-    I2C: Read<Error = E> + Write<Error = E> + WriteRead<Error = E>,
-    D: DelayMs<u32>,
+    I2C: I2c<Error = E>,
+    D: DelayNs,
 {
-    pub fn new(i2c: I2C, delay: D) -> Self {
+    /// Reinterprets the sensor in a new lifecycle state, moving the owned bus
+    /// and delay across unchanged.
+    fn into_state<S2>(self) -> Sgp41<I2C, D, S2> {
         Sgp41 {
-            i2c,
-            delay,
-            temperature_offset: 0,
+            i2c: self.i2c,
+            delay: self.delay,
+            temperature_offset: self.temperature_offset,
+            default_humidity: self.default_humidity,
+            conditioning_duration_ms: self.conditioning_duration_ms,
+            _state: PhantomData,
         }
     }
 
-    /// This command starts the conditioning, i.e., the VOC pixel will be
-    /// operated at the default temperature and humidity (+25 deg.C, 50% rH) as it is by calling the
-    /// measure_raw command  while  the  NOx  pixel  will
-    /// be  operated  at  a  different  temperature  for  conditioning.  This
-    /// command returns only the measured raw signal of the VOC pixel SRAW_VOC as u16.
-    pub fn execute_conditioning(&mut self) -> Result<u16, Error<E>> {
-        let mut buf = [0; 3];
-        self.read_cmd_args(Command::ExecuteConditioning, &[0x8000, 0x6666], &mut buf)?;
-        Ok(u16::from_be_bytes([buf[0], buf[1]]))
-    }
-
-    pub fn measure_raw(&mut self) -> Result<RawSensorData, Error<E>> {
-        let mut buf = [0; 6];
-        self.read_cmd_args(Command::MeasureRawSignals, &[0x8000, 0x6666], &mut buf)?;
-        let voc_ticks = u16::from_be_bytes([buf[0], buf[1]]);
-        let nox_ticks = u16::from_be_bytes([buf[3], buf[4]]);
-        let data = RawSensorData {
-            voc_ticks,
-            nox_ticks,
-        };
-        Ok(data)
-    }
-
-    pub fn measure_raw_compensated(
-        &mut self,
-        humidity: u8,
-        temperature: i16,
-    ) -> Result<RawSensorData, Error<E>> {
-        assert!(humidity <= 100 && temperature >= -45 && temperature <= 130);
-        let humidity_ticks = humidity as u16 * u16::MAX / 100;
-        let temperature_ticks =
-            (temperature + 45 + self.temperature_offset) as u16 * u16::MAX / 175;
-
-        let mut buf = [0; 6];
-        self.read_cmd_args(
-            Command::MeasureRawSignals,
-            &[humidity_ticks, temperature_ticks],
-            &mut buf,
-        )?;
-        let voc_ticks = u16::from_be_bytes([buf[0], buf[1]]);
-        let nox_ticks = u16::from_be_bytes([buf[3], buf[4]]);
-        let data = RawSensorData {
-            voc_ticks,
-            nox_ticks,
-        };
-        Ok(data)
+    /// Recommended conditioning window configured through [`Sgp41Builder`].
+    pub fn conditioning_duration_ms(&self) -> u32 {
+        self.conditioning_duration_ms
     }
 
     /// This command triggers the built-in self-test checking for integrity
@@ -82,7 +84,7 @@ where
     /// test as 2 bytes (+ 1 CRC byte).
     pub fn execute_self_test(&mut self) -> Result<(), Error<E>> {
         let mut buf = [0; 3];
-        self.read_cmd(Command::ExecuteConditioning, &mut buf)?;
+        self.read_cmd(Command::ExecuteSelfTest, &mut buf)?;
         // There is only two significant bits
         let err = u16::from_be_bytes([buf[0], buf[1]]) & 0b11;
         match err {
@@ -94,12 +96,6 @@ where
         }
     }
 
-    /// This command turns the hotplate off and stops the measurement.
-    /// Subsequently, the sensor enters the idle mode.
-    pub fn turn_heater_off(&mut self) -> Result<(), Error<E>> {
-        self.write_cmd(Command::TurnHeaterOff)
-    }
-
     pub fn get_serial_number(&mut self) -> Result<u64, Error<E>> {
         let mut buf = [0; 9];
         self.read_cmd(Command::GetSerialNumber, &mut buf)?;
@@ -128,7 +124,7 @@ where
     /// Writes command without additional arguments.
     fn write_cmd(&mut self, cmd: Command) -> Result<(), Error<E>> {
         let (command, delay) = cmd.as_tuple();
-        i2c::write_command(&mut self.i2c, SGP41_I2C_ADDRESS, command).map_err(Error::I2c)?;
+        i2c::write_command_u16(&mut self.i2c, SGP41_I2C_ADDRESS, command).map_err(Error::I2c)?;
         self.delay.delay_ms(delay);
         Ok(())
     }
@@ -176,13 +172,268 @@ where
         i2c::read_words_with_crc(&mut self.i2c, SGP41_I2C_ADDRESS, data)?;
         Ok(())
     }
+
+    /// Writes a command with arguments but does not block for its measurement
+    /// window, leaving the caller's scheduler in charge of the wait.
+    #[cfg(feature = "low_power")]
+    fn write_cmd_args_no_delay(&mut self, cmd: Command, args: &[u16]) -> Result<(), Error<E>> {
+        let (command, _) = cmd.as_tuple();
+
+        let mut buf = [0; 8];
+        assert!(command.to_ne_bytes().len() + args.len() * 3 <= buf.len());
+
+        buf[0..2].copy_from_slice(&command.to_be_bytes());
+
+        let mut i = 2;
+        for arg in args {
+            let end = i + 2;
+            let be_arg = arg.to_be_bytes();
+            buf[i..end].copy_from_slice(&be_arg);
+            buf[end] = crc8::calculate(&be_arg);
+            i += 3;
+        }
+
+        self.i2c
+            .write(SGP41_I2C_ADDRESS, &buf[0..i])
+            .map_err(Error::I2c)
+    }
+}
+
+impl<I2C, D, E> Sgp41<I2C, D, mode::Idle>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    pub fn new(i2c: I2C, delay: D) -> Self {
+        Sgp41 {
+            i2c,
+            delay,
+            temperature_offset: 0,
+            default_humidity: None,
+            conditioning_duration_ms: DEFAULT_CONDITIONING_MS,
+            _state: PhantomData,
+        }
+    }
+
+    /// This command starts the conditioning, i.e., the VOC pixel will be
+    /// operated at the default temperature and humidity (+25 deg.C, 50% rH) as it is by calling the
+    /// measure_raw command  while  the  NOx  pixel  will
+    /// be  operated  at  a  different  temperature  for  conditioning.
+    ///
+    /// Conditioning must not be run for longer than 10 s; this method issues
+    /// the command and then holds for the configured conditioning window
+    /// (see [`Sgp41Builder::with_conditioning_duration`], clamped to the 10 s
+    /// maximum) before returning a [`Conditioning`](mode::Conditioning) sensor.
+    pub fn execute_conditioning(
+        mut self,
+    ) -> Result<Sgp41<I2C, D, mode::Conditioning>, ModeChangeError<E, Self>> {
+        let mut buf = [0; 3];
+        if let Err(error) = self.read_cmd_args(Command::ExecuteConditioning, &[0x8000, 0x6666], &mut buf) {
+            return Err(ModeChangeError { error, dev: self });
+        }
+        self.delay.delay_ms(self.conditioning_duration_ms);
+        Ok(self.into_state())
+    }
+
+    /// Re-enters measurement from standby by re-running conditioning, as the
+    /// baseline requires the VOC pixel to be reconditioned before the gas
+    /// index is trustworthy again. Counterpart to
+    /// [`standby`](Sgp41::standby).
+    #[cfg(feature = "low_power")]
+    pub fn resume(
+        self,
+    ) -> Result<Sgp41<I2C, D, mode::Conditioning>, ModeChangeError<E, Self>> {
+        self.execute_conditioning()
+    }
+}
+
+/// Builder collecting the persistent configuration before a sensor is created.
+///
+/// Mirrors the configuration-first flow the datasheet recommends: set the
+/// `temperature_offset`, an optional default relative humidity that
+/// [`measure`](Sgp41::measure) feeds into a compensated read automatically, and
+/// the conditioning window (bounded to the 10 s maximum), then
+/// [`build`](Sgp41Builder::build) an [`Idle`](mode::Idle) sensor around an I2C
+/// bus and delay. This replaces the bare `new` + `set_temperature_offset` flow.
+pub struct Sgp41Builder {
+    temperature_offset: i16,
+    default_humidity: Option<u8>,
+    conditioning_duration_ms: u32,
+}
+
+impl Default for Sgp41Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sgp41Builder {
+    /// Starts a builder with the driver defaults: no temperature offset, no
+    /// default humidity, and the 10 s conditioning window.
+    pub fn new() -> Self {
+        Sgp41Builder {
+            temperature_offset: 0,
+            default_humidity: None,
+            conditioning_duration_ms: DEFAULT_CONDITIONING_MS,
+        }
+    }
+
+    /// Sets the persistent temperature offset in deg.C, useful when on-board
+    /// heat sources (battery charger, motor, etc) bias the reading.
+    pub fn with_temperature_offset(mut self, offset: i16) -> Self {
+        self.temperature_offset = offset;
+        self
+    }
+
+    /// Sets a default relative humidity in %rH that [`measure`](Sgp41::measure)
+    /// feeds into a compensated read when no humidity is supplied explicitly.
+    /// Values are clamped to the valid 0..=100 %rH range so a configured
+    /// default can never panic a later measurement.
+    pub fn with_default_humidity(mut self, humidity: u8) -> Self {
+        self.default_humidity = Some(humidity.min(100));
+        self
+    }
+
+    /// Sets the conditioning window in ms, clamped to the 10 s maximum the
+    /// datasheet mandates.
+    pub fn with_conditioning_duration(mut self, duration_ms: u32) -> Self {
+        self.conditioning_duration_ms = duration_ms.min(DEFAULT_CONDITIONING_MS);
+        self
+    }
+
+    /// Consumes the builder, returning an idle sensor around `i2c` and `delay`.
+    pub fn build<I2C, D, E>(self, i2c: I2C, delay: D) -> Sgp41<I2C, D, mode::Idle>
+    where
+        I2C: I2c<Error = E>,
+        D: DelayNs,
+    {
+        Sgp41 {
+            i2c,
+            delay,
+            temperature_offset: self.temperature_offset,
+            default_humidity: self.default_humidity,
+            conditioning_duration_ms: self.conditioning_duration_ms,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<I2C, D, E> Sgp41<I2C, D, mode::Conditioning>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+{
+    /// Leaves conditioning and enters the measuring state. Raw reads issue the
+    /// same `MeasureRawSignals` command in both states, so this is only a
+    /// lifecycle transition and performs no bus access.
+    pub fn start_measurement(self) -> Sgp41<I2C, D, mode::Measuring> {
+        self.into_state()
+    }
+}
+
+impl<I2C, D, E, State> Sgp41<I2C, D, State>
+where
+    I2C: I2c<Error = E>,
+    D: DelayNs,
+    State: MeasurementState,
+{
+    pub fn measure_raw(&mut self) -> Result<RawSensorData, Error<E>> {
+        let mut buf = [0; 6];
+        self.read_cmd_args(Command::MeasureRawSignals, &[0x8000, 0x6666], &mut buf)?;
+        let voc_ticks = u16::from_be_bytes([buf[0], buf[1]]);
+        let nox_ticks = u16::from_be_bytes([buf[3], buf[4]]);
+        let data = RawSensorData {
+            voc_ticks,
+            nox_ticks,
+        };
+        Ok(data)
+    }
+
+    pub fn measure_raw_compensated(
+        &mut self,
+        humidity: u8,
+        temperature: i16,
+    ) -> Result<RawSensorData, Error<E>> {
+        assert!(humidity <= 100 && temperature >= -45 && temperature <= 130);
+        let humidity_ticks = (humidity as u32 * u16::MAX as u32 / 100) as u16;
+        let temperature_ticks =
+            (temperature + 45 + self.temperature_offset) as u16 * u16::MAX / 175;
+
+        let mut buf = [0; 6];
+        self.read_cmd_args(
+            Command::MeasureRawSignals,
+            &[humidity_ticks, temperature_ticks],
+            &mut buf,
+        )?;
+        let voc_ticks = u16::from_be_bytes([buf[0], buf[1]]);
+        let nox_ticks = u16::from_be_bytes([buf[3], buf[4]]);
+        let data = RawSensorData {
+            voc_ticks,
+            nox_ticks,
+        };
+        Ok(data)
+    }
+
+    /// Measures using the default relative humidity configured through
+    /// [`Sgp41Builder::with_default_humidity`], falling back to an
+    /// uncompensated read when none was set.
+    pub fn measure(&mut self, temperature: i16) -> Result<RawSensorData, Error<E>> {
+        match self.default_humidity {
+            Some(humidity) => self.measure_raw_compensated(humidity, temperature),
+            None => self.measure_raw(),
+        }
+    }
+
+    /// Issues `MeasureRawSignals` without blocking for the built-in 50 ms
+    /// measurement window. Poll [`read_measurement`](Sgp41::read_measurement)
+    /// once the window has elapsed so a cooperative scheduler is not forced to
+    /// sleep inside the driver.
+    #[cfg(feature = "low_power")]
+    pub fn measure_raw_non_blocking(&mut self) -> Result<(), Error<E>> {
+        self.write_cmd_args_no_delay(Command::MeasureRawSignals, &[0x8000, 0x6666])
+    }
+
+    /// Reads the result of a previously issued
+    /// [`measure_raw_non_blocking`](Sgp41::measure_raw_non_blocking). The
+    /// caller is responsible for waiting the 50 ms measurement window first.
+    #[cfg(feature = "low_power")]
+    pub fn read_measurement(&mut self) -> Result<RawSensorData, Error<E>> {
+        let mut buf = [0; 6];
+        i2c::read_words_with_crc(&mut self.i2c, SGP41_I2C_ADDRESS, &mut buf)?;
+        let voc_ticks = u16::from_be_bytes([buf[0], buf[1]]);
+        let nox_ticks = u16::from_be_bytes([buf[3], buf[4]]);
+        Ok(RawSensorData {
+            voc_ticks,
+            nox_ticks,
+        })
+    }
+
+    /// Parks the sensor for battery operation by turning the hotplate off and
+    /// returning to [`Idle`](mode::Idle). Because the 1 Hz cadence is broken,
+    /// the gas index baseline is no longer valid; call
+    /// [`resume`](Sgp41::resume) to recondition before measuring again.
+    #[cfg(feature = "low_power")]
+    pub fn standby(self) -> Result<Sgp41<I2C, D, mode::Idle>, ModeChangeError<E, Self>> {
+        self.turn_heater_off()
+    }
+
+    /// This command turns the hotplate off and stops the measurement.
+    /// Subsequently, the sensor enters the idle mode.
+    pub fn turn_heater_off(
+        mut self,
+    ) -> Result<Sgp41<I2C, D, mode::Idle>, ModeChangeError<E, Self>> {
+        if let Err(error) = self.write_cmd(Command::TurnHeaterOff) {
+            return Err(ModeChangeError { error, dev: self });
+        }
+        Ok(self.into_state())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use embedded_hal_mock as hal;
+    use embedded_hal_mock::eh1 as hal;
 
-    use self::hal::delay::MockNoop as DelayMock;
+    use self::hal::delay::NoopDelay as DelayMock;
     use self::hal::i2c::{Mock as I2cMock, Transaction};
     use super::*;
 
@@ -198,11 +449,12 @@ mod tests {
                 vec![0xbe, 0xef, 0x92, 0xbe, 0xef, 0x92, 0xbe, 0xef, 0x92],
             ),
         ];
-        let mock = I2cMock::new(&expectations);
-        let mut sensor = Sgp41::new(mock, DelayMock);
+        let mut mock = I2cMock::new(&expectations);
+        let mut sensor = Sgp41::new(mock.clone(), DelayMock);
         // Act
         let serial = sensor.get_serial_number().unwrap();
         // Assert
         assert_eq!(serial, 0xbeefbeefbeef);
+        mock.done();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,10 @@
+/// Raw, uncalibrated gas signals as reported by the SGP41.
+///
+/// Both values are sensor ticks and drift with the MOX baseline; feed them
+/// through the [`crate::gas_index`] algorithm to obtain a processed index.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawSensorData {
+    pub voc_ticks: u16,
+    pub nox_ticks: u16,
+}